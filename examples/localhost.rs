@@ -1,4 +1,4 @@
-use jenkins_trace::{Config, CrumbUrl, JenkinsTrace};
+use jenkins_trace::{Auth, Config, CrumbUrl, JenkinsTrace};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -6,11 +6,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config {
         url: "http://localhost:8080/job/foo/5/logText/progressiveText".to_string(),
         crumb_url: CrumbUrl::Json("http://localhost:8080/crumbIssuer/api/json".to_string()),
-        auth: Some(("root".to_string(), Some("root".to_string()))),
+        auth: Some(Auth::Basic {
+            user: "root".to_string(),
+            password: Some("root".to_string()),
+        }),
+        tls: Default::default(),
+        request_timeout: std::time::Duration::from_secs(30),
+        max_retries: 3,
+        initial_backoff: std::time::Duration::from_millis(500),
     };
 
     // create jenkins tracer
-    let mut trace = JenkinsTrace::new(config);
+    let mut trace = JenkinsTrace::new(config)?;
 
     loop {
         match trace.next_trace().await? {
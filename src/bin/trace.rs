@@ -1,5 +1,6 @@
-use jenkins_trace::{Config, CrumbUrl, JenkinsTrace};
-use std::{io::Cursor, time::Duration};
+use futures::StreamExt;
+use jenkins_trace::{Auth, BuildKey, Config, CrumbUrl, JenkinsTrace, TlsConfig, TraceSet};
+use std::{collections::HashMap, io::Cursor, path::PathBuf, time::Duration};
 use structopt::StructOpt;
 use tokio::{
     io::{copy, stdout},
@@ -15,23 +16,68 @@ struct Opt {
     /// Jenkins project name.
     #[structopt(short, long)]
     job: String,
-    /// Numeric ID of the build.
-    #[structopt(short, long)]
-    build: u64,
+    /// Build to trace: a numeric ID or a symbolic selector such as
+    /// `lastBuild`, `lastSuccessfulBuild`, or `lastCompletedBuild`. Repeat the
+    /// flag (optionally as `job#build`) to tail several builds at once.
+    #[structopt(short, long, number_of_values = 1, required = true)]
+    build: Vec<String>,
     /// Use HTML output.
     #[structopt(short = "H", long)]
     html: bool,
-    /// Jenkins login credentials (username:password).
+    /// Jenkins login credentials (username[:secret]). The secret is resolved
+    /// from JENKINS_TOKEN/~/.netrc or prompted for when omitted.
     #[structopt(short, long)]
     user: Option<String>,
+    /// Treat the --user secret as a Jenkins API token rather than a password.
+    #[structopt(long)]
+    token: bool,
+    /// Authenticate with a raw bearer token (resolved like --user when empty).
+    #[structopt(long)]
+    bearer: Option<String>,
     /// Delay between requests in seconds.
     #[structopt(short, long, default_value = "1.0")]
     delay: f64,
+    /// Per-request timeout in seconds.
+    #[structopt(long, default_value = "30.0")]
+    timeout: f64,
+    /// Number of times a transient failure is retried.
+    #[structopt(long, default_value = "3")]
+    max_retries: u32,
+    /// Initial retry backoff in seconds (doubled, then capped, per retry).
+    #[structopt(long, default_value = "0.5")]
+    backoff: f64,
+    /// Additional CA certificate (PEM) to trust.
+    #[structopt(long)]
+    cacert: Option<PathBuf>,
+    /// Accept invalid/self-signed certificates.
+    #[structopt(long)]
+    insecure: bool,
+    /// Pin the server leaf certificate to this SHA-256 fingerprint (hex).
+    #[structopt(long)]
+    pinned_fingerprint: Option<String>,
 }
 
 impl Opt {
-    /// Return job progressive log endpoint.
-    fn url(&self) -> String {
+    /// Parse the repeated `--build` values into `(job, build)` pairs, honouring
+    /// the optional `job#build` form and falling back to `--job` otherwise.
+    fn builds(&self) -> Vec<BuildKey> {
+        self.build
+            .iter()
+            .map(|spec| match spec.split_once('#') {
+                Some((job, build)) => BuildKey {
+                    job: job.to_string(),
+                    build: build.to_string(),
+                },
+                None => BuildKey {
+                    job: self.job.clone(),
+                    build: spec.clone(),
+                },
+            })
+            .collect()
+    }
+
+    /// Return the progressive log endpoint for a given build.
+    fn url(&self, key: &BuildKey) -> String {
         let endpoint = if self.html {
             "progressiveHtml"
         } else {
@@ -40,8 +86,8 @@ impl Opt {
         let url = format!(
             "{host}/job/{job}/{build}/logText/{endpoint}",
             host = self.host,
-            job = self.job,
-            build = self.build,
+            job = key.job,
+            build = key.build,
             endpoint = endpoint,
         );
         Url::parse(&url)
@@ -49,6 +95,19 @@ impl Opt {
             .expect("Error parsing Jenkins log URL")
     }
 
+    /// Build the [`Config`] for a given build.
+    fn config(&self, key: &BuildKey, tls: &TlsConfig) -> Config {
+        Config {
+            url: self.url(key),
+            crumb_url: self.crumb_url(),
+            auth: self.auth(),
+            tls: tls.clone(),
+            request_timeout: Duration::from_secs_f64(self.timeout),
+            max_retries: self.max_retries,
+            initial_backoff: Duration::from_secs_f64(self.backoff),
+        }
+    }
+
     /// Return crumb endpoint.
     fn crumb_url(&self) -> CrumbUrl {
         let url = format!("{}/crumbIssuer/api/json", self.host);
@@ -59,31 +118,133 @@ impl Opt {
         )
     }
 
-    /// Return Basic auth.
-    fn auth(&self) -> Option<(String, Option<String>)> {
-        self.user.as_ref().map(|auth| {
-            let mut split = auth.split(':');
+    /// Return the chosen credential.
+    fn auth(&self) -> Option<Auth> {
+        if let Some(bearer) = &self.bearer {
+            return Some(Auth::Bearer {
+                token: opt_secret(bearer),
+            });
+        }
+        self.user.as_ref().map(|spec| {
+            let mut split = spec.split(':');
             let user = split.next().unwrap().to_string();
-            let pass = split.next().map(|p| p.to_string());
-            (user, pass)
+            let secret = split.next().and_then(opt_secret);
+            if self.token {
+                Auth::Token { user, token: secret }
+            } else {
+                Auth::Basic {
+                    user,
+                    password: secret,
+                }
+            }
         })
     }
+
+    /// Return TLS options.
+    fn tls(&self) -> Result<TlsConfig, Box<dyn std::error::Error>> {
+        let root_certificates = match &self.cacert {
+            Some(path) => vec![std::fs::read(path)?],
+            None => vec![],
+        };
+        Ok(TlsConfig {
+            accept_invalid_certs: self.insecure,
+            root_certificates,
+            pinned_fingerprint: self.pinned_fingerprint.clone(),
+        })
+    }
+}
+
+/// Map an empty string to `None` so the crate resolves the secret itself.
+fn opt_secret(secret: &str) -> Option<String> {
+    if secret.is_empty() {
+        None
+    } else {
+        Some(secret.to_string())
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let opt = Opt::from_args();
+    let tls = opt.tls()?;
+    let builds = opt.builds();
+
+    // A single build tails verbatim; its exit code reflects the build result.
+    if let [key] = builds.as_slice() {
+        return single(&opt, key, &tls).await;
+    }
+
+    // Several builds: multiplex their chunks, prefixing each line.
+    let mut set = TraceSet::new(&tls)?;
+    for key in &builds {
+        set.add(key.clone(), opt.config(key, &tls), Duration::from_secs_f64(opt.delay))?;
+    }
+
+    // Resolve symbolic selectors to concrete numbers before tailing, then keep
+    // the resolved configs around to report build results afterwards.
+    set.resolve().await?;
+    let resolved = set.configs();
+
+    let mut stream = set.into_stream();
+    let mut partial: HashMap<BuildKey, String> = HashMap::new();
+    while let Some((key, result)) = stream.next().await {
+        let chunk = result?;
+        let buf = partial.entry(key.clone()).or_default();
+        buf.push_str(&String::from_utf8_lossy(&chunk));
+        while let Some(idx) = buf.find('\n') {
+            let line: String = buf.drain(..=idx).collect();
+            print!("[{}#{}] {}", key.job, key.build, line);
+        }
+    }
+    // Flush any trailing partial lines.
+    for (key, buf) in partial {
+        if !buf.is_empty() {
+            println!("[{}#{}] {}", key.job, key.build, buf);
+        }
+    }
 
-    let mut trace = JenkinsTrace::new(Config {
-        url: opt.url(),
-        crumb_url: opt.crumb_url(),
-        auth: opt.auth(),
-    });
+    // Report a failed build through a nonzero exit code, as in single mode. The
+    // resolved configs already carry the resolved credential, so no reprompt.
+    let mut failed = false;
+    for (key, config) in resolved {
+        if JenkinsTrace::new(config)?
+            .build_result()
+            .await?
+            .map_or(false, |result| result.is_failure())
+        {
+            eprintln!("[{}#{}] build failed", key.job, key.build);
+            failed = true;
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+// Trace a single build, writing its log to stdout and exiting nonzero on a
+// failed build.
+async fn single(opt: &Opt, key: &BuildKey, tls: &TlsConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut trace = JenkinsTrace::new(opt.config(key, tls))?;
+
+    // Resolve a symbolic selector (lastBuild, …) to a concrete build number
+    // before tracing; numeric builds trace directly.
+    if key.build.parse::<u64>().is_err() {
+        trace.resolve_build().await?;
+    }
 
     while let Some(bytes) = trace.next_trace().await? {
         copy(&mut Cursor::new(bytes), &mut stdout()).await?;
         delay_for(Duration::from_secs_f64(opt.delay)).await;
     }
 
+    // Report a failed build through a nonzero exit code.
+    if let Some(result) = trace.build_result().await? {
+        if result.is_failure() {
+            std::process::exit(1);
+        }
+    }
+
     Ok(())
 }
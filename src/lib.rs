@@ -1,8 +1,20 @@
 #![deny(unused)]
 
 use bytes::Bytes;
-use reqwest::{multipart::Form, Client, RequestBuilder, Response};
-use std::fmt;
+use futures::{Stream, StreamExt};
+use reqwest::{multipart::Form, Certificate, Client, RequestBuilder, Response, StatusCode};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    io::{self, Cursor, Read},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{io::AsyncRead, time::delay_for};
 
 /// CSRF Crumb request endpoint.
 ///
@@ -56,9 +68,162 @@ impl fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
-/// Basic auth username & password.
-pub type Auth = (String, Option<String>);
+/// Outcome of a Jenkins build, as reported by the JSON API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildResult {
+    /// The build is still running (`building: true`).
+    Building,
+    Success,
+    Failure,
+    Unstable,
+    Aborted,
+}
+
+impl BuildResult {
+    /// Whether this result should be treated as a build failure (so callers,
+    /// e.g. the CLI, can exit nonzero).
+    pub fn is_failure(self) -> bool {
+        matches!(
+            self,
+            BuildResult::Failure | BuildResult::Unstable | BuildResult::Aborted
+        )
+    }
+}
+
+/// Subset of `/job/<project>/<build>/api/json` we care about.
+#[derive(serde::Deserialize)]
+struct BuildMeta {
+    number: u64,
+    building: bool,
+    result: Option<String>,
+}
+
+impl BuildMeta {
+    // Map the raw `building`/`result` fields onto a [`BuildResult`].
+    fn build_result(&self) -> Option<BuildResult> {
+        if self.building {
+            return Some(BuildResult::Building);
+        }
+        match self.result.as_deref() {
+            Some("SUCCESS") => Some(BuildResult::Success),
+            Some("FAILURE") => Some(BuildResult::Failure),
+            Some("UNSTABLE") => Some(BuildResult::Unstable),
+            Some("ABORTED") => Some(BuildResult::Aborted),
+            _ => None,
+        }
+    }
+}
+
+/// How a request authenticates against Jenkins.
+///
+/// When the secret field is left `None`, it is resolved lazily in
+/// [`JenkinsTrace::new`] from the `JENKINS_TOKEN` environment variable, a
+/// `~/.netrc` entry keyed by the host, or — if a TTY is attached — an
+/// interactive no-echo prompt.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Auth {
+    /// HTTP basic auth with a username and optional password.
+    Basic {
+        user: String,
+        password: Option<String>,
+    },
+    /// A Jenkins API token used as the basic-auth password for `user`.
+    Token { user: String, token: Option<String> },
+    /// A raw bearer token set via `Authorization: Bearer`.
+    Bearer { token: Option<String> },
+}
+
+// Environment variable consulted for a missing secret.
+const TOKEN_ENV: &str = "JENKINS_TOKEN";
+
+impl Auth {
+    // Resolve a missing secret from the environment, ~/.netrc, or a prompt.
+    fn resolve(&mut self, host: &str) -> Result<(), Error> {
+        match self {
+            Auth::Basic { user, password } if password.is_none() => {
+                *password = resolve_secret(host, Some(user))?;
+            }
+            Auth::Token { user, token } if token.is_none() => {
+                *token = resolve_secret(host, Some(user))?;
+            }
+            Auth::Bearer { token } if token.is_none() => {
+                *token = resolve_secret(host, None)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Apply the credential to an outgoing request.
+    fn apply(&self, req: RequestBuilder) -> RequestBuilder {
+        match self {
+            Auth::Basic { user, password } => req.basic_auth(user, password.as_ref()),
+            Auth::Token { user, token } => req.basic_auth(user, token.as_ref()),
+            Auth::Bearer {
+                token: Some(token), ..
+            } => req.bearer_auth(token),
+            Auth::Bearer { token: None } => req,
+        }
+    }
+}
+
+// Look up a secret for `host`, trying the environment, ~/.netrc, then a prompt.
+fn resolve_secret(host: &str, user: Option<&str>) -> Result<Option<String>, Error> {
+    if let Ok(secret) = std::env::var(TOKEN_ENV) {
+        if !secret.is_empty() {
+            return Ok(Some(secret));
+        }
+    }
+    if let Some(secret) = netrc_password(host) {
+        return Ok(Some(secret));
+    }
+    if atty::is(atty::Stream::Stdin) {
+        let prompt = match user {
+            Some(user) => format!("Password for {}@{}: ", user, host),
+            None => format!("Token for {}: ", host),
+        };
+        let secret = rpassword::read_password_from_tty(Some(&prompt))
+            .map_err(|_| Error::Jenkins("failed to read password from tty"))?;
+        if !secret.is_empty() {
+            return Ok(Some(secret));
+        }
+    }
+    Ok(None)
+}
+
+// Password from the `~/.netrc` entry whose machine matches `host`, if any.
+fn netrc_password(host: &str) -> Option<String> {
+    let path = dirs::home_dir()?.join(".netrc");
+    let file = std::fs::File::open(path).ok()?;
+    let netrc = netrc::Netrc::parse(std::io::BufReader::new(file)).ok()?;
+    netrc
+        .hosts
+        .into_iter()
+        .find(|(name, _)| name == host)
+        .and_then(|(_, machine)| machine.password)
+}
+
 type Crumb = (String, String);
+/// Crumb cache keyed by crumb-issuer endpoint, so several tracers hitting the
+/// same host share a single cached crumb.
+type CrumbCache = Arc<Mutex<HashMap<String, Crumb>>>;
+
+/// TLS options applied when building the underlying [`reqwest::Client`].
+///
+/// Jenkins is almost always served over HTTPS, frequently behind an internal
+/// CA or a self-signed certificate, so the defaults here mirror the most
+/// common hardening knobs.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct TlsConfig {
+    /// Accept invalid/self-signed certificates (maps to
+    /// `ClientBuilder::danger_accept_invalid_certs`).
+    pub accept_invalid_certs: bool,
+    /// Additional root certificates, each in PEM encoding.
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Expected SHA-256 (hex) of the leaf certificate's DER encoding. When set,
+    /// chain validation is bypassed in favour of a direct fingerprint match.
+    pub pinned_fingerprint: Option<String>,
+}
 
 /// Jenkins job
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -71,6 +236,15 @@ pub struct Config {
     pub crumb_url: CrumbUrl,
     /// HTTP basic auth.
     pub auth: Option<Auth>,
+    /// TLS options.
+    pub tls: TlsConfig,
+    /// Per-request timeout. A long poll against a busy Jenkins can otherwise
+    /// hang indefinitely.
+    pub request_timeout: Duration,
+    /// Number of times a transient failure is retried before giving up.
+    pub max_retries: u32,
+    /// Initial backoff delay; doubled (and capped) after each retry.
+    pub initial_backoff: Duration,
 }
 
 /// A type to read the log of a given jenkins build.
@@ -80,24 +254,117 @@ pub struct JenkinsTrace {
     // To keep track of the # of bytes read so far.
     offset: u64,
     ended: bool,
-    crumb: Option<Crumb>,
+    crumb: CrumbCache,
 }
 
 impl JenkinsTrace {
     const MORE_DATA_FIELD: &'static str = "X-More-Data";
     const TEXT_SIZE_FIELD: &'static str = "X-Text-Size";
+    // Upper bound applied to the backoff each time it is doubled.
+    const BACKOFF_CAP: Duration = Duration::from_secs(30);
 
     /// Create a new jenkins trace with the given job parameters.
-    pub fn new(config: Config) -> Self {
-        Self {
+    ///
+    /// Fails if the [`TlsConfig`] is malformed (e.g. an unparseable root
+    /// certificate or pinned fingerprint).
+    pub fn new(config: Config) -> Result<Self, Error> {
+        let client = build_client(&config.tls)?;
+        Self::assemble(config, client, Arc::new(Mutex::new(HashMap::new())))
+    }
+
+    // Construct a tracer over a (possibly shared) client and crumb cache,
+    // resolving any missing credential secret now that we know the host.
+    fn assemble(mut config: Config, client: Client, crumb: CrumbCache) -> Result<Self, Error> {
+        let host = reqwest::Url::parse(&config.url)
+            .map_err(|_| Error::Jenkins("invalid job url"))?
+            .host_str()
+            .unwrap_or_default()
+            .to_string();
+        if let Some(auth) = config.auth.as_mut() {
+            auth.resolve(&host)?;
+        }
+
+        Ok(Self {
             config,
-            client: Client::new(),
+            client,
             offset: 0,
             ended: false,
-            crumb: None,
+            crumb,
+        })
+    }
+
+    /// Convert the tracer into a [`Stream`] of log chunks.
+    ///
+    /// The stream drives the same progressive-log poll loop as
+    /// [`next_trace`](JenkinsTrace::next_trace), yielding each chunk as it
+    /// arrives and terminating once Jenkins stops setting `X-More-Data`.
+    pub fn into_stream(self) -> TraceStream {
+        TraceStream {
+            trace: Some(self),
+            next: None,
         }
     }
 
+    /// Convert the tracer into a [`tokio::io::AsyncRead`], so the log can be
+    /// piped straight into the `tokio::io` toolset, e.g.
+    /// `tokio::io::copy(&mut trace.into_async_read(), &mut stdout())`.
+    pub fn into_async_read(self) -> TraceAsyncRead {
+        TraceAsyncRead {
+            stream: self.into_stream(),
+            chunk: Cursor::new(Bytes::new()),
+        }
+    }
+
+    /// Resolve a symbolic build selector (e.g. `lastBuild`,
+    /// `lastSuccessfulBuild`, `lastCompletedBuild`) in the configured url to a
+    /// concrete build number, rewriting the url in place so the trace follows
+    /// that build. A numeric build is resolved to itself. Returns the concrete
+    /// build number.
+    pub async fn resolve_build(&mut self) -> Result<u64, Error> {
+        let number = self.build_meta().await?.number;
+
+        // Replace the build segment (the part between `/job/<project>/` and
+        // `/logText/`) with the concrete number.
+        if let Some(idx) = self.config.url.find("/logText/") {
+            let suffix = self.config.url[idx..].to_string();
+            let prefix = &self.config.url[..idx];
+            if let Some(parent) = prefix.rfind('/').map(|p| &prefix[..p]) {
+                self.config.url = format!("{}/{}{}", parent, number, suffix);
+            }
+        }
+
+        Ok(number)
+    }
+
+    /// Query the build's JSON API and return its [`BuildResult`], e.g. to tell
+    /// a failed build apart from a clean tail. A running build yields
+    /// `Some(BuildResult::Building)`; `None` if a finished build reports no
+    /// result or one not listed in [`BuildResult`].
+    pub async fn build_result(&self) -> Result<Option<BuildResult>, Error> {
+        Ok(self.build_meta().await?.build_result())
+    }
+
+    // Derive the build's `api/json` endpoint from the progressive-log url.
+    fn api_url(&self) -> String {
+        let base = match self.config.url.find("/logText/") {
+            Some(idx) => &self.config.url[..idx],
+            None => self.config.url.trim_end_matches('/'),
+        };
+        format!("{}/api/json", base)
+    }
+
+    // Fetch and deserialize the build metadata.
+    async fn build_meta(&self) -> Result<BuildMeta, Error> {
+        let body = self
+            .base_request(&self.api_url())
+            .send()
+            .await
+            .and_then(Response::error_for_status)?
+            .text()
+            .await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
     /// Returns a future that resolves to the next log trace.
     /// Returns None if the trace has ended.
     pub async fn next_trace(&mut self) -> Result<Option<Bytes>, Error> {
@@ -124,18 +391,18 @@ impl JenkinsTrace {
         Ok(Some(response.bytes().await?))
     }
 
-    // create request with basic auth
+    // create request with the configured credential and the per-request timeout
     fn base_request(&self, url: &str) -> RequestBuilder {
-        let req = self.client.get(url);
-        if let Some((user, passwd)) = &self.config.auth {
-            req.basic_auth(user, passwd.as_ref())
-        } else {
-            req
+        let req = self.client.get(url).timeout(self.config.request_timeout);
+        match &self.config.auth {
+            Some(auth) => auth.apply(req),
+            None => req,
         }
     }
 
     async fn csrf_crumb_future(&mut self) -> Result<Crumb, Error> {
-        if let Some(crumb) = &self.crumb {
+        let key = self.config.crumb_url.as_str().to_string();
+        if let Some(crumb) = self.crumb.lock().unwrap().get(&key) {
             return Ok(crumb.clone());
         }
 
@@ -162,22 +429,355 @@ impl JenkinsTrace {
             CrumbUrl::Json(_) => serde_json::from_str(&body)?,
         };
 
-        self.crumb = Some((crumb_request_field, crumb));
-        Ok(self.crumb.clone().unwrap())
+        let crumb = (crumb_request_field, crumb);
+        self.crumb.lock().unwrap().insert(key, crumb.clone());
+        Ok(crumb)
     }
 
     async fn trace_request_future(&mut self) -> Result<Response, Error> {
-        // request CSRF crumb
-        let (crumb_field, crumb) = self.csrf_crumb_future().await?;
-
-        // request next log
-        // fails if response code isn't 2xx
-        Ok(self
-            .base_request(&self.config.url)
-            .multipart(Form::new().text("start", format!("{}", self.offset)))
-            .header(&crumb_field, &crumb)
-            .send()
-            .await
-            .and_then(Response::error_for_status)?)
+        let mut attempt = 0;
+        let mut backoff = self.config.initial_backoff;
+        // A crumb can expire mid-build; we refresh it at most once per call,
+        // for free (the refresh doesn't count against the transient budget).
+        let mut crumb_refreshed = false;
+
+        loop {
+            // request CSRF crumb
+            let (crumb_field, crumb) = self.csrf_crumb_future().await?;
+
+            // request next log
+            let result = self
+                .base_request(&self.config.url)
+                .multipart(Form::new().text("start", format!("{}", self.offset)))
+                .header(&crumb_field, &crumb)
+                .send()
+                .await;
+
+            match result {
+                Ok(response) => {
+                    let status = response.status();
+
+                    // A rejected crumb comes back as 403 with a tell-tale body.
+                    if status == StatusCode::FORBIDDEN && !crumb_refreshed {
+                        let body = response.text().await?;
+                        if is_crumb_rejection(&body) {
+                            self.crumb
+                                .lock()
+                                .unwrap()
+                                .remove(self.config.crumb_url.as_str());
+                            crumb_refreshed = true;
+                            continue;
+                        }
+                        return Err(Error::Jenkins("Forbidden"));
+                    }
+
+                    if is_transient_status(status) && attempt < self.config.max_retries {
+                        attempt += 1;
+                        delay_for(backoff).await;
+                        backoff = (backoff * 2).min(Self::BACKOFF_CAP);
+                        continue;
+                    }
+
+                    // fails if response code isn't 2xx
+                    return Ok(response.error_for_status()?);
+                }
+                Err(err) => {
+                    if is_transient(&err) && attempt < self.config.max_retries {
+                        attempt += 1;
+                        delay_for(backoff).await;
+                        backoff = (backoff * 2).min(Self::BACKOFF_CAP);
+                        continue;
+                    }
+                    return Err(err.into());
+                }
+            }
+        }
+    }
+}
+
+// Jenkins answers a stale crumb with 403 and this message in the body.
+fn is_crumb_rejection(body: &str) -> bool {
+    body.contains("No valid crumb") || body.contains("Invalid crumb")
+}
+
+// Server-side statuses worth retrying: request timeout and any 5xx.
+fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT || status.is_server_error()
+}
+
+// Timeouts and connection resets (no associated status) are transient too.
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.status().map_or(true, is_transient_status)
+}
+
+// Build the reqwest client, applying the TLS options from `tls`.
+fn build_client(tls: &TlsConfig) -> Result<Client, Error> {
+    let mut builder = Client::builder();
+
+    if tls.accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    for pem in &tls.root_certificates {
+        builder = builder.add_root_certificate(Certificate::from_pem(pem)?);
+    }
+
+    // A pin short-circuits normal chain validation: we only care that the
+    // presented leaf matches the fingerprint we were given.
+    if let Some(fingerprint) = &tls.pinned_fingerprint {
+        let verifier = PinnedCertVerifier::new(fingerprint)?;
+        let mut tls_config = rustls::ClientConfig::new();
+        tls_config
+            .dangerous()
+            .set_certificate_verifier(Arc::new(verifier));
+        builder = builder.use_preconfigured_tls(tls_config);
+    }
+
+    Ok(builder.build()?)
+}
+
+// Decode a hex string into its raw bytes, rejecting anything non-hex. Colons
+// and whitespace are stripped first, so the `AA:BB:CC:…` form emitted by
+// `openssl x509 -fingerprint` and Jenkins is accepted.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, Error> {
+    let hex: String = hex
+        .chars()
+        .filter(|c| *c != ':' && !c.is_whitespace())
+        .collect();
+    if hex.len() % 2 != 0 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::Jenkins("invalid pinned fingerprint"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| Error::Jenkins("invalid pinned fingerprint"))
+        })
+        .collect()
+}
+
+// A rustls certificate verifier that accepts a connection only when the SHA-256
+// of the presented leaf certificate's DER encoding matches a pinned value.
+struct PinnedCertVerifier {
+    fingerprint: Vec<u8>,
+}
+
+impl PinnedCertVerifier {
+    fn new(fingerprint: &str) -> Result<Self, Error> {
+        Ok(Self {
+            fingerprint: decode_hex(fingerprint)?,
+        })
+    }
+}
+
+impl rustls::ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        _roots: &rustls::RootCertStore,
+        presented_certs: &[rustls::Certificate],
+        _dns_name: webpki::DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> Result<rustls::ServerCertVerified, rustls::TLSError> {
+        let leaf = presented_certs
+            .first()
+            .ok_or(rustls::TLSError::NoCertificatesPresented)?;
+        if Sha256::digest(&leaf.0).as_slice() == self.fingerprint.as_slice() {
+            Ok(rustls::ServerCertVerified::assertion())
+        } else {
+            Err(rustls::TLSError::General(
+                "certificate fingerprint mismatch".to_string(),
+            ))
+        }
     }
 }
+
+/// Boxed future that drives a [`JenkinsTrace`] to its next chunk and hands the
+/// tracer back so the stream can reuse it for the following request.
+type NextTraceFuture =
+    Pin<Box<dyn Future<Output = (JenkinsTrace, Result<Option<Bytes>, Error>)> + Send>>;
+
+/// A [`Stream`] of log chunks produced by a [`JenkinsTrace`].
+///
+/// Created with [`JenkinsTrace::into_stream`]. Yields `Ok(bytes)` for every
+/// progressive-log chunk and ends (`None`) once the last response lacked the
+/// `X-More-Data` header.
+pub struct TraceStream {
+    trace: Option<JenkinsTrace>,
+    next: Option<NextTraceFuture>,
+}
+
+impl Stream for TraceStream {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            // Drive the in-flight next-chunk future, if any.
+            if let Some(next) = this.next.as_mut() {
+                let (trace, result) = match next.as_mut().poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(ready) => ready,
+                };
+                this.trace = Some(trace);
+                this.next = None;
+                return match result {
+                    Ok(Some(bytes)) => Poll::Ready(Some(Ok(bytes))),
+                    Ok(None) => Poll::Ready(None),
+                    Err(err) => Poll::Ready(Some(Err(err))),
+                };
+            }
+
+            // No future pending: issue the next request.
+            match this.trace.take() {
+                None => return Poll::Ready(None),
+                Some(mut trace) => {
+                    this.next = Some(Box::pin(async move {
+                        let result = trace.next_trace().await;
+                        (trace, result)
+                    }));
+                }
+            }
+        }
+    }
+}
+
+/// A [`tokio::io::AsyncRead`] adapter over a [`TraceStream`].
+///
+/// Created with [`JenkinsTrace::into_async_read`]. A chunk larger than the
+/// caller's buffer is drained across successive `poll_read` calls from an
+/// internal cursor before the next request is issued, and EOF is reported once
+/// the underlying stream is exhausted.
+pub struct TraceAsyncRead {
+    stream: TraceStream,
+    chunk: Cursor<Bytes>,
+}
+
+impl AsyncRead for TraceAsyncRead {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            // Drain whatever is left of the current chunk first.
+            if (this.chunk.position() as usize) < this.chunk.get_ref().len() {
+                return Poll::Ready(this.chunk.read(buf));
+            }
+
+            // Current chunk exhausted: pull the next one from the stream.
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => return Poll::Ready(Ok(0)),
+                Poll::Ready(Some(Ok(bytes))) => this.chunk = Cursor::new(bytes),
+                Poll::Ready(Some(Err(err))) => {
+                    return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err)))
+                }
+            }
+        }
+    }
+}
+
+/// Identifies one build tailed by a [`TraceSet`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BuildKey {
+    pub job: String,
+    pub build: String,
+}
+
+// A single build tracked by a [`TraceSet`].
+struct Child {
+    key: BuildKey,
+    trace: JenkinsTrace,
+    delay: Duration,
+}
+
+/// Tails several builds at once, multiplexing their log chunks.
+///
+/// Every child keeps its own offset and end state but shares a single
+/// [`reqwest::Client`] and a single crumb cache per host, so watching a matrix
+/// job or a fan-out of downstream builds doesn't multiply crumb-issuer round
+/// trips. Each build is polled with an independent delay, so one stalled build
+/// doesn't hold up the others.
+pub struct TraceSet {
+    children: Vec<Child>,
+    client: Client,
+    crumb: CrumbCache,
+}
+
+impl TraceSet {
+    /// Create an empty set whose children share a freshly built client.
+    ///
+    /// The TLS options are applied once here; per-child [`Config::tls`] is
+    /// ignored in favour of this shared client.
+    pub fn new(tls: &TlsConfig) -> Result<Self, Error> {
+        Ok(Self {
+            children: Vec::new(),
+            client: build_client(tls)?,
+            crumb: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Add a build to tail, polled with its own `delay` between chunks.
+    pub fn add(&mut self, key: BuildKey, config: Config, delay: Duration) -> Result<(), Error> {
+        let trace = JenkinsTrace::assemble(config, self.client.clone(), self.crumb.clone())?;
+        self.children.push(Child { key, trace, delay });
+        Ok(())
+    }
+
+    /// Resolve every child's symbolic selector (`lastBuild`, …) to a concrete
+    /// build number, so the log is tailed — and prefixed — with the real
+    /// number. Numeric builds are left untouched.
+    pub async fn resolve(&mut self) -> Result<(), Error> {
+        for child in &mut self.children {
+            if child.key.build.parse::<u64>().is_err() {
+                let number = child.trace.resolve_build().await?;
+                child.key.build = number.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// Snapshot each child's key and (resolved) [`Config`], so callers can
+    /// query [`JenkinsTrace::build_result`] after the combined stream — which
+    /// consumes the set — is exhausted, and apply the same failed-build exit
+    /// code as the single-build path.
+    pub fn configs(&self) -> Vec<(BuildKey, Config)> {
+        self.children
+            .iter()
+            .map(|child| (child.key.clone(), child.trace.config.clone()))
+            .collect()
+    }
+
+    /// Drive all children as a combined stream of `(BuildKey, chunk)` items.
+    pub fn into_stream(self) -> impl Stream<Item = (BuildKey, Result<Bytes, Error>)> {
+        let children = self
+            .children
+            .into_iter()
+            .map(|child| child_stream(child.key, child.trace, child.delay).boxed());
+        futures::stream::select_all(children)
+    }
+}
+
+// Turn a single build into a stream of its log chunks tagged with its key, applying the
+// per-build delay before every request after the first and ending on the first
+// error or once the build's log is exhausted.
+fn child_stream(
+    key: BuildKey,
+    trace: JenkinsTrace,
+    delay: Duration,
+) -> impl Stream<Item = (BuildKey, Result<Bytes, Error>)> {
+    futures::stream::unfold(Some((trace, false)), move |state| {
+        let key = key.clone();
+        async move {
+            let (mut trace, started) = state?;
+            if started {
+                delay_for(delay).await;
+            }
+            match trace.next_trace().await {
+                Ok(Some(bytes)) => Some(((key, Ok(bytes)), Some((trace, true)))),
+                Ok(None) => None,
+                Err(err) => Some(((key, Err(err)), None)),
+            }
+        }
+    })
+}